@@ -17,6 +17,154 @@ pub struct MediaType {
     pub extensions: IndexMap<String, serde_json::Value>,
 }
 
+/// A single media range parsed out of an `Accept` header, e.g.
+/// `application/json;q=0.9`.
+struct MediaRange {
+    type_: String,
+    subtype: String,
+    q: f32,
+}
+
+impl MediaRange {
+    /// Returns how specifically this range matches `type_/subtype`, or
+    /// `None` if it doesn't match at all. Higher is more specific: an exact
+    /// match beats `type/*`, which beats `*/*`.
+    fn specificity_against(&self, type_: &str, subtype: &str) -> Option<u8> {
+        if self.type_ == "*" && self.subtype == "*" {
+            Some(0)
+        } else if self.type_.eq_ignore_ascii_case(type_) && self.subtype == "*" {
+            Some(1)
+        } else if self.type_.eq_ignore_ascii_case(type_) && self.subtype.eq_ignore_ascii_case(subtype) {
+            Some(2)
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_accept(accept: &str) -> Vec<MediaRange> {
+    accept
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let (type_, subtype) = parts.next()?.trim().split_once('/')?;
+            let mut q = 1.0f32;
+            for param in parts {
+                if let Some(value) = param.trim().strip_prefix("q=") {
+                    q = value.trim().parse().unwrap_or(1.0);
+                }
+            }
+            Some(MediaRange {
+                type_: type_.trim().to_owned(),
+                subtype: subtype.trim().to_owned(),
+                q,
+            })
+        })
+        .collect()
+}
+
+/// The best `(specificity, q)` among `ranges` that matches `type_/subtype`,
+/// if any of them do.
+fn best_match(ranges: &[MediaRange], type_: &str, subtype: &str) -> Option<(u8, f32)> {
+    ranges
+        .iter()
+        .filter_map(|range| range.specificity_against(type_, subtype).map(|spec| (spec, range.q)))
+        .max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+}
+
+/// Selects the entry in `content` that best matches an `Accept` header,
+/// per the specificity rules of
+/// [RFC 7231 §5.3.2](https://www.rfc-editor.org/rfc/rfc7231#section-5.3.2):
+/// an exact `type/subtype` match beats `type/*`, which beats `*/*`; among
+/// equally specific matches the higher `q` value wins; and if that's still
+/// a tie, the entry that appears first in `content` wins. A range with
+/// `q=0` is treated as explicitly unacceptable. Returns `None` if nothing
+/// in `content` is acceptable.
+///
+/// # Examples
+///
+/// ```
+/// # use indexmap::IndexMap;
+/// # use openapiv3::v3_0::{negotiate_media_type, MediaType};
+/// let mut content = IndexMap::new();
+/// content.insert("application/json".to_owned(), MediaType::default());
+/// content.insert("text/plain".to_owned(), MediaType::default());
+///
+/// let (key, _) = negotiate_media_type("text/plain, application/*;q=0.8", &content).unwrap();
+/// assert_eq!(key, "text/plain");
+/// ```
+pub fn negotiate_media_type<'a>(
+    accept: &str,
+    content: &'a IndexMap<String, MediaType>,
+) -> Option<(&'a String, &'a MediaType)> {
+    let ranges = parse_accept(accept);
+    let mut best: Option<(u8, f32, &'a String, &'a MediaType)> = None;
+
+    for (key, media_type) in content.iter() {
+        let Some((type_, subtype)) = key.split_once('/') else {
+            continue;
+        };
+        let Some((specificity, q)) = best_match(&ranges, type_, subtype) else {
+            continue;
+        };
+        if q <= 0.0 {
+            continue;
+        }
+        let is_better = match &best {
+            None => true,
+            Some((best_specificity, best_q, ..)) => {
+                (specificity, q).partial_cmp(&(*best_specificity, *best_q)) == Some(std::cmp::Ordering::Greater)
+            }
+        };
+        if is_better {
+            best = Some((specificity, q, key, media_type));
+        }
+    }
+
+    best.map(|(_, _, key, media_type)| (key, media_type))
+}
+
+#[cfg(test)]
+mod negotiate_media_type_tests {
+    use super::*;
+
+    fn content(keys: &[&str]) -> IndexMap<String, MediaType> {
+        keys.iter().map(|k| (k.to_string(), MediaType::default())).collect()
+    }
+
+    #[test]
+    fn q_zero_on_the_more_specific_range_excludes_that_entry() {
+        // `application/json` matches both the exact, explicitly-excluded
+        // range and the `*/*` fallback; RFC 7231 says q=0 on the more
+        // specific range wins, so this entry must not be chosen even
+        // though a lower-specificity acceptable range also matches it.
+        let content = content(&["application/json", "text/plain"]);
+        let (key, _) = negotiate_media_type("application/json;q=0, */*;q=0.5", &content).unwrap();
+        assert_eq!(key, "text/plain");
+    }
+
+    #[test]
+    fn wildcard_falls_back_to_the_first_entry_when_nothing_more_specific_matches() {
+        let content = content(&["application/json", "text/plain"]);
+        let (key, _) = negotiate_media_type("*/*", &content).unwrap();
+        assert_eq!(key, "application/json");
+    }
+
+    #[test]
+    fn equal_rank_matches_tiebreak_on_map_insertion_order() {
+        let content = content(&["text/plain", "application/json"]);
+        let (key, _) =
+            negotiate_media_type("application/json;q=0.5, text/plain;q=0.5", &content).unwrap();
+        assert_eq!(key, "text/plain");
+    }
+
+    #[test]
+    fn nothing_acceptable_returns_none() {
+        let content = content(&["application/json"]);
+        assert!(negotiate_media_type("text/plain", &content).is_none());
+    }
+}
+
 #[cfg(feature = "conversions")]
 use crate::v3_1;
 
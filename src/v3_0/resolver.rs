@@ -0,0 +1,713 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::v3_0::*;
+use indexmap::IndexMap;
+
+/// Errors produced while resolving `$ref` pointers against a document's
+/// [`Components`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// The `$ref` string is not a well-formed local JSON Pointer fragment
+    /// (`#/components/<kind>/<name>`).
+    MalformedReference(String),
+    /// The pointer is well-formed but names an entry that does not exist.
+    DanglingReference(String),
+    /// [`ResolveMode::Strict`] encountered a reference it has no way to
+    /// resolve, such as a pointer into another file.
+    UnresolvableReference(String),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::MalformedReference(r) => write!(f, "malformed $ref: {}", r),
+            ResolveError::DanglingReference(r) => write!(f, "$ref does not resolve to anything: {}", r),
+            ResolveError::UnresolvableReference(r) => write!(f, "cannot resolve $ref: {}", r),
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// Controls how [`resolve`] and [`resolve_components`] treat references
+/// they cannot resolve against the local `Components` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResolveMode {
+    /// Resolve every `$ref` that points at `#/components/...` in this
+    /// document. References into other files (e.g. `other.yaml#/...`) are
+    /// left untouched as plain [`ReferenceOr::Reference`]s.
+    #[default]
+    ComponentsOnly,
+    /// Like [`ResolveMode::ComponentsOnly`], but treat any reference that
+    /// isn't a local `#/components/...` pointer as an error instead of
+    /// silently leaving it unresolved.
+    Strict,
+}
+
+/// Resolves every internal `$ref` reachable from `spec`, turning
+/// [`ReferenceOr::Reference`] nodes into
+/// [`ReferenceOr::DereferencedReference`].
+///
+/// This dereferences both the shared `Components` table and every
+/// operation under `paths` (their parameters, request bodies, responses,
+/// and callbacks), so the primary use case — walking an operation's schema
+/// without hand-resolving its `$ref`s first — doesn't require a second
+/// pass. A path item that is itself a `$ref` (pointing outside this
+/// document, since Path Item Objects aren't part of `Components`) is left
+/// as-is.
+pub fn resolve(mut spec: OpenAPI, mode: ResolveMode) -> Result<OpenAPI, ResolveError> {
+    if let Some(components) = &spec.components {
+        spec.components = Some(resolve_components(components, mode)?);
+    }
+    let components = spec.components.clone().unwrap_or_default();
+    spec.paths = resolve_paths(&spec.paths, &components, mode)?;
+    Ok(spec)
+}
+
+fn resolve_paths(paths: &Paths, components: &Components, mode: ResolveMode) -> Result<Paths, ResolveError> {
+    Ok(Paths {
+        paths: paths
+            .paths
+            .iter()
+            .map(|(path, item)| Ok((path.clone(), resolve_path_item_ref(item.clone(), components, mode)?)))
+            .collect::<Result<_, ResolveError>>()?,
+        extensions: paths.extensions.clone(),
+    })
+}
+
+fn resolve_path_item_ref(
+    item: ReferenceOr<PathItem>,
+    components: &Components,
+    mode: ResolveMode,
+) -> Result<ReferenceOr<PathItem>, ResolveError> {
+    match item {
+        ReferenceOr::Item(item) => Ok(ReferenceOr::Item(resolve_path_item(item, components, mode)?)),
+        ReferenceOr::DereferencedReference { reference, item } => Ok(ReferenceOr::DereferencedReference {
+            reference,
+            item: resolve_path_item(item, components, mode)?,
+        }),
+        // Path Item Objects aren't part of `Components`, so a `$ref` here
+        // always points outside this document; there's nothing local to
+        // resolve it against.
+        reference @ ReferenceOr::Reference { .. } => Ok(reference),
+    }
+}
+
+fn resolve_path_item(mut item: PathItem, components: &Components, mode: ResolveMode) -> Result<PathItem, ResolveError> {
+    item.get = item.get.map(|op| resolve_operation(op, components, mode)).transpose()?;
+    item.put = item.put.map(|op| resolve_operation(op, components, mode)).transpose()?;
+    item.post = item.post.map(|op| resolve_operation(op, components, mode)).transpose()?;
+    item.delete = item.delete.map(|op| resolve_operation(op, components, mode)).transpose()?;
+    item.options = item.options.map(|op| resolve_operation(op, components, mode)).transpose()?;
+    item.head = item.head.map(|op| resolve_operation(op, components, mode)).transpose()?;
+    item.patch = item.patch.map(|op| resolve_operation(op, components, mode)).transpose()?;
+    item.trace = item.trace.map(|op| resolve_operation(op, components, mode)).transpose()?;
+    item.parameters = item
+        .parameters
+        .into_iter()
+        .map(|p| resolve_ref_or(p, components, mode, &mut Stack::new()))
+        .collect::<Result<_, ResolveError>>()?;
+    Ok(item)
+}
+
+fn resolve_operation(mut op: Operation, components: &Components, mode: ResolveMode) -> Result<Operation, ResolveError> {
+    op.parameters = op
+        .parameters
+        .into_iter()
+        .map(|p| resolve_ref_or(p, components, mode, &mut Stack::new()))
+        .collect::<Result<_, ResolveError>>()?;
+    if let Some(request_body) = op.request_body {
+        op.request_body = Some(resolve_ref_or(request_body, components, mode, &mut Stack::new())?);
+    }
+    op.responses = resolve_responses(op.responses, components, mode)?;
+    op.callbacks = op
+        .callbacks
+        .into_iter()
+        .map(|(name, callback)| Ok((name, resolve_ref_or(callback, components, mode, &mut Stack::new())?)))
+        .collect::<Result<_, ResolveError>>()?;
+    Ok(op)
+}
+
+fn resolve_responses(mut responses: Responses, components: &Components, mode: ResolveMode) -> Result<Responses, ResolveError> {
+    if let Some(default) = responses.default {
+        responses.default = Some(resolve_ref_or(default, components, mode, &mut Stack::new())?);
+    }
+    responses.responses = responses
+        .responses
+        .into_iter()
+        .map(|(status, response)| Ok((status, resolve_ref_or(response, components, mode, &mut Stack::new())?)))
+        .collect::<Result<_, ResolveError>>()?;
+    Ok(responses)
+}
+
+/// Resolves every internal `$ref` inside `components` against itself.
+///
+/// Recursive schemas (a schema that, directly or transitively, refers back
+/// to itself) are detected via the stack of pointers currently being
+/// resolved: the node that would close the cycle is left as a plain
+/// `Reference` rather than inlined, so resolution always terminates.
+pub fn resolve_components(components: &Components, mode: ResolveMode) -> Result<Components, ResolveError> {
+    Ok(Components {
+        security_schemes: resolve_map::<SecurityScheme>(&components.security_schemes, components, mode)?,
+        responses: resolve_map::<Response>(&components.responses, components, mode)?,
+        parameters: resolve_map::<Parameter>(&components.parameters, components, mode)?,
+        examples: resolve_map::<Example>(&components.examples, components, mode)?,
+        request_bodies: resolve_map::<RequestBody>(&components.request_bodies, components, mode)?,
+        headers: resolve_map::<Header>(&components.headers, components, mode)?,
+        schemas: resolve_map::<Schema>(&components.schemas, components, mode)?,
+        links: resolve_map::<Link>(&components.links, components, mode)?,
+        callbacks: resolve_map::<Callback>(&components.callbacks, components, mode)?,
+        extensions: components.extensions.clone(),
+    })
+}
+
+type Stack = HashSet<String>;
+
+/// A type that lives in one of the named maps on [`Components`] and can
+/// therefore be the target of a `#/components/<kind>/<name>` pointer.
+trait Component: Clone + Sized {
+    const KIND: &'static str;
+
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>>;
+
+    /// Resolves any `$ref`s nested inside an already-unwrapped item (e.g. a
+    /// schema's `properties`, or a response's `content`).
+    fn resolve_nested(
+        item: Self,
+        components: &Components,
+        mode: ResolveMode,
+        stack: &mut Stack,
+    ) -> Result<Self, ResolveError>;
+}
+
+impl Component for Example {
+    const KIND: &'static str = "examples";
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.examples
+    }
+    fn resolve_nested(
+        item: Self,
+        _components: &Components,
+        _mode: ResolveMode,
+        _stack: &mut Stack,
+    ) -> Result<Self, ResolveError> {
+        Ok(item)
+    }
+}
+
+impl Component for SecurityScheme {
+    const KIND: &'static str = "securitySchemes";
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.security_schemes
+    }
+    fn resolve_nested(
+        item: Self,
+        _components: &Components,
+        _mode: ResolveMode,
+        _stack: &mut Stack,
+    ) -> Result<Self, ResolveError> {
+        Ok(item)
+    }
+}
+
+impl Component for Link {
+    const KIND: &'static str = "links";
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.links
+    }
+    fn resolve_nested(
+        item: Self,
+        _components: &Components,
+        _mode: ResolveMode,
+        _stack: &mut Stack,
+    ) -> Result<Self, ResolveError> {
+        Ok(item)
+    }
+}
+
+impl Component for Callback {
+    const KIND: &'static str = "callbacks";
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.callbacks
+    }
+    // Callbacks hang a full PathItem (and therefore arbitrarily nested
+    // operations, parameters and schemas) off of every key; reuse the same
+    // path-item walk that `resolve_paths` uses for the document's own paths.
+    fn resolve_nested(
+        item: Self,
+        components: &Components,
+        mode: ResolveMode,
+        _stack: &mut Stack,
+    ) -> Result<Self, ResolveError> {
+        item.into_iter()
+            .map(|(expression, path_item)| Ok((expression, resolve_path_item(path_item, components, mode)?)))
+            .collect()
+    }
+}
+
+impl Component for Header {
+    const KIND: &'static str = "headers";
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.headers
+    }
+    fn resolve_nested(
+        mut item: Self,
+        components: &Components,
+        mode: ResolveMode,
+        stack: &mut Stack,
+    ) -> Result<Self, ResolveError> {
+        item.format = resolve_parameter_schema_or_content(item.format, components, mode, stack)?;
+        item.examples = resolve_map_values::<Example>(item.examples, components, mode, stack)?;
+        Ok(item)
+    }
+}
+
+impl Component for Parameter {
+    const KIND: &'static str = "parameters";
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.parameters
+    }
+    fn resolve_nested(
+        item: Self,
+        components: &Components,
+        mode: ResolveMode,
+        stack: &mut Stack,
+    ) -> Result<Self, ResolveError> {
+        let resolve_data = |mut data: ParameterData| -> Result<ParameterData, ResolveError> {
+            data.format = resolve_parameter_schema_or_content(data.format, components, mode, stack)?;
+            data.examples = resolve_map_values::<Example>(data.examples, components, mode, stack)?;
+            Ok(data)
+        };
+        Ok(match item {
+            Parameter::Query { parameter_data, allow_reserved, style, allow_empty_value } => {
+                Parameter::Query {
+                    parameter_data: resolve_data(parameter_data)?,
+                    allow_reserved,
+                    style,
+                    allow_empty_value,
+                }
+            }
+            Parameter::Header { parameter_data, style } => Parameter::Header {
+                parameter_data: resolve_data(parameter_data)?,
+                style,
+            },
+            Parameter::Path { parameter_data, style } => Parameter::Path {
+                parameter_data: resolve_data(parameter_data)?,
+                style,
+            },
+            Parameter::Cookie { parameter_data, style } => Parameter::Cookie {
+                parameter_data: resolve_data(parameter_data)?,
+                style,
+            },
+        })
+    }
+}
+
+impl Component for RequestBody {
+    const KIND: &'static str = "requestBodies";
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.request_bodies
+    }
+    fn resolve_nested(
+        mut item: Self,
+        components: &Components,
+        mode: ResolveMode,
+        stack: &mut Stack,
+    ) -> Result<Self, ResolveError> {
+        item.content = resolve_content_map(item.content, components, mode, stack)?;
+        Ok(item)
+    }
+}
+
+impl Component for Response {
+    const KIND: &'static str = "responses";
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.responses
+    }
+    fn resolve_nested(
+        mut item: Self,
+        components: &Components,
+        mode: ResolveMode,
+        stack: &mut Stack,
+    ) -> Result<Self, ResolveError> {
+        item.headers = resolve_map_values::<Header>(item.headers, components, mode, stack)?;
+        item.content = resolve_content_map(item.content, components, mode, stack)?;
+        item.links = resolve_map_values::<Link>(item.links, components, mode, stack)?;
+        Ok(item)
+    }
+}
+
+impl Component for Schema {
+    const KIND: &'static str = "schemas";
+    fn map(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+        &components.schemas
+    }
+    fn resolve_nested(
+        mut item: Self,
+        components: &Components,
+        mode: ResolveMode,
+        stack: &mut Stack,
+    ) -> Result<Self, ResolveError> {
+        item.schema_kind = match item.schema_kind {
+            SchemaKind::Type(Type::Object(mut obj)) => {
+                obj.properties = obj
+                    .properties
+                    .into_iter()
+                    .map(|(name, schema)| Ok((name, resolve_boxed_schema(schema, components, mode, stack)?)))
+                    .collect::<Result<_, ResolveError>>()?;
+                if let Some(AdditionalProperties::Schema(schema)) = obj.additional_properties {
+                    obj.additional_properties = Some(AdditionalProperties::Schema(
+                        resolve_boxed_schema(*schema, components, mode, stack).map(Box::new)?,
+                    ));
+                }
+                SchemaKind::Type(Type::Object(obj))
+            }
+            SchemaKind::Type(Type::Array(mut arr)) => {
+                if let Some(items) = arr.items {
+                    arr.items = Some(resolve_boxed_schema(items, components, mode, stack)?);
+                }
+                SchemaKind::Type(Type::Array(arr))
+            }
+            SchemaKind::AllOf { all_of } => SchemaKind::AllOf {
+                all_of: resolve_schema_list(all_of, components, mode, stack)?,
+            },
+            SchemaKind::AnyOf { any_of } => SchemaKind::AnyOf {
+                any_of: resolve_schema_list(any_of, components, mode, stack)?,
+            },
+            SchemaKind::OneOf { one_of } => SchemaKind::OneOf {
+                one_of: resolve_schema_list(one_of, components, mode, stack)?,
+            },
+            SchemaKind::Not { not } => SchemaKind::Not {
+                not: Box::new(resolve_ref_or(*not, components, mode, stack)?),
+            },
+            other => other,
+        };
+        Ok(item)
+    }
+}
+
+fn resolve_schema_list(
+    schemas: Vec<ReferenceOr<Schema>>,
+    components: &Components,
+    mode: ResolveMode,
+    stack: &mut Stack,
+) -> Result<Vec<ReferenceOr<Schema>>, ResolveError> {
+    schemas
+        .into_iter()
+        .map(|s| resolve_ref_or(s, components, mode, stack))
+        .collect()
+}
+
+fn resolve_boxed_schema(
+    schema: ReferenceOr<Box<Schema>>,
+    components: &Components,
+    mode: ResolveMode,
+    stack: &mut Stack,
+) -> Result<ReferenceOr<Box<Schema>>, ResolveError> {
+    let resolved = resolve_ref_or(schema.unbox(), components, mode, stack)?;
+    Ok(match resolved {
+        ReferenceOr::Reference { reference } => ReferenceOr::Reference { reference },
+        ReferenceOr::Item(item) => ReferenceOr::Item(Box::new(item)),
+        ReferenceOr::DereferencedReference { reference, item } => {
+            ReferenceOr::DereferencedReference { reference, item: Box::new(item) }
+        }
+    })
+}
+
+fn resolve_parameter_schema_or_content(
+    format: ParameterSchemaOrContent,
+    components: &Components,
+    mode: ResolveMode,
+    stack: &mut Stack,
+) -> Result<ParameterSchemaOrContent, ResolveError> {
+    Ok(match format {
+        ParameterSchemaOrContent::Schema(schema) => {
+            ParameterSchemaOrContent::Schema(resolve_ref_or(schema, components, mode, stack)?)
+        }
+        ParameterSchemaOrContent::Content(content) => {
+            ParameterSchemaOrContent::Content(resolve_content_map(content, components, mode, stack)?)
+        }
+    })
+}
+
+fn resolve_content_map(
+    content: IndexMap<String, MediaType>,
+    components: &Components,
+    mode: ResolveMode,
+    stack: &mut Stack,
+) -> Result<IndexMap<String, MediaType>, ResolveError> {
+    content
+        .into_iter()
+        .map(|(name, media_type)| Ok((name, resolve_media_type(media_type, components, mode, stack)?)))
+        .collect()
+}
+
+fn resolve_media_type(
+    mut media_type: MediaType,
+    components: &Components,
+    mode: ResolveMode,
+    stack: &mut Stack,
+) -> Result<MediaType, ResolveError> {
+    if let Some(schema) = media_type.schema {
+        media_type.schema = Some(resolve_ref_or(schema, components, mode, stack)?);
+    }
+    media_type.examples = resolve_map_values::<Example>(media_type.examples, components, mode, stack)?;
+    Ok(media_type)
+}
+
+fn resolve_map_values<C: Component>(
+    map: IndexMap<String, ReferenceOr<C>>,
+    components: &Components,
+    mode: ResolveMode,
+    stack: &mut Stack,
+) -> Result<IndexMap<String, ReferenceOr<C>>, ResolveError> {
+    map.into_iter()
+        .map(|(name, value)| Ok((name, resolve_ref_or(value, components, mode, stack)?)))
+        .collect()
+}
+
+/// Resolves `components.<C::KIND>` into a fresh map, seeding the cycle
+/// stack with each entry's own pointer before descending into it so that a
+/// schema which `$ref`s itself is caught rather than expanded forever.
+fn resolve_map<C: Component>(
+    map: &IndexMap<String, ReferenceOr<C>>,
+    components: &Components,
+    mode: ResolveMode,
+) -> Result<IndexMap<String, ReferenceOr<C>>, ResolveError> {
+    map.iter()
+        .map(|(name, entry)| {
+            let pointer = format!("#/components/{}/{}", C::KIND, encode_token(name));
+            let mut stack = Stack::new();
+            stack.insert(pointer);
+            let resolved = match entry.clone() {
+                ReferenceOr::Item(item) => ReferenceOr::Item(C::resolve_nested(item, components, mode, &mut stack)?),
+                other => resolve_ref_or(other, components, mode, &mut stack)?,
+            };
+            Ok((name.clone(), resolved))
+        })
+        .collect()
+}
+
+fn resolve_ref_or<C: Component>(
+    r: ReferenceOr<C>,
+    components: &Components,
+    mode: ResolveMode,
+    stack: &mut Stack,
+) -> Result<ReferenceOr<C>, ResolveError> {
+    match r {
+        ReferenceOr::Reference { reference } => match parse_pointer(&reference, mode)? {
+            None => Ok(ReferenceOr::Reference { reference }),
+            Some((kind, _)) if kind != C::KIND => Err(ResolveError::MalformedReference(format!(
+                "{} does not point at a {} entry",
+                reference,
+                C::KIND
+            ))),
+            Some((_, name)) => {
+                if stack.contains(&reference) {
+                    return Ok(ReferenceOr::Reference { reference });
+                }
+                let target = C::map(components)
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| ResolveError::DanglingReference(reference.clone()))?;
+                stack.insert(reference.clone());
+                let resolved = resolve_ref_or(target, components, mode, stack)?;
+                stack.remove(&reference);
+                let item = match resolved {
+                    ReferenceOr::Item(item) => item,
+                    ReferenceOr::DereferencedReference { item, .. } => item,
+                    // The named entry is itself an unresolved alias because
+                    // resolving it would close a cycle; surface that as-is.
+                    unresolved @ ReferenceOr::Reference { .. } => return Ok(unresolved),
+                };
+                Ok(ReferenceOr::DereferencedReference { reference, item })
+            }
+        },
+        ReferenceOr::Item(item) => Ok(ReferenceOr::Item(C::resolve_nested(item, components, mode, stack)?)),
+        ReferenceOr::DereferencedReference { reference, item } => Ok(ReferenceOr::DereferencedReference {
+            reference,
+            item: C::resolve_nested(item, components, mode, stack)?,
+        }),
+    }
+}
+
+/// Splits a `$ref` string into the `components` sub-map it names (e.g.
+/// `"schemas"`) and the (JSON-Pointer-decoded) entry name, or `None` if the
+/// reference falls outside `#/components/...` (an external file, or a
+/// pointer into some other part of the document).
+fn parse_pointer(reference: &str, mode: ResolveMode) -> Result<Option<(&'static str, String)>, ResolveError> {
+    let fragment = match reference.split_once('#') {
+        Some(("", fragment)) => fragment,
+        Some(_) => return unresolvable(reference, mode),
+        None => return Err(ResolveError::MalformedReference(reference.to_owned())),
+    };
+    let mut segments = fragment.split('/').filter(|s| !s.is_empty());
+    let (Some(root), Some(kind), Some(name), None) =
+        (segments.next(), segments.next(), segments.next(), segments.next())
+    else {
+        return unresolvable(reference, mode);
+    };
+    if root != "components" {
+        return unresolvable(reference, mode);
+    }
+    let kind = match kind {
+        "schemas" => "schemas",
+        "responses" => "responses",
+        "parameters" => "parameters",
+        "examples" => "examples",
+        "requestBodies" => "requestBodies",
+        "headers" => "headers",
+        "links" => "links",
+        "callbacks" => "callbacks",
+        "securitySchemes" => "securitySchemes",
+        _ => return unresolvable(reference, mode),
+    };
+    Ok(Some((kind, decode_token(name))))
+}
+
+fn unresolvable(reference: &str, mode: ResolveMode) -> Result<Option<(&'static str, String)>, ResolveError> {
+    match mode {
+        ResolveMode::Strict => Err(ResolveError::UnresolvableReference(reference.to_owned())),
+        ResolveMode::ComponentsOnly => Ok(None),
+    }
+}
+
+fn decode_token(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+fn encode_token(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema_all_of(refs: Vec<&str>) -> Schema {
+        Schema {
+            schema_data: SchemaData::default(),
+            schema_kind: SchemaKind::AllOf {
+                all_of: refs.into_iter().map(ReferenceOr::ref_).collect(),
+            },
+        }
+    }
+
+    fn all_of_refs(schema: &Schema) -> &[ReferenceOr<Schema>] {
+        match &schema.schema_kind {
+            SchemaKind::AllOf { all_of } => all_of,
+            _ => panic!("expected a SchemaKind::AllOf"),
+        }
+    }
+
+    #[test]
+    fn self_referential_schema_is_left_as_a_reference() {
+        let mut components = Components::default();
+        components.schemas.insert(
+            "Foo".to_owned(),
+            ReferenceOr::Item(schema_all_of(vec!["#/components/schemas/Foo"])),
+        );
+
+        let resolved = resolve_components(&components, ResolveMode::ComponentsOnly).unwrap();
+        let foo = resolved.schemas.get("Foo").unwrap().as_item().unwrap();
+        assert!(
+            matches!(all_of_refs(foo)[0], ReferenceOr::Reference { .. }),
+            "the ref that closes the cycle must stay a plain Reference, not be inlined"
+        );
+    }
+
+    #[test]
+    fn mutually_recursive_schemas_terminate() {
+        let mut components = Components::default();
+        components.schemas.insert(
+            "Foo".to_owned(),
+            ReferenceOr::Item(schema_all_of(vec!["#/components/schemas/Bar"])),
+        );
+        components.schemas.insert(
+            "Bar".to_owned(),
+            ReferenceOr::Item(schema_all_of(vec!["#/components/schemas/Foo"])),
+        );
+
+        let resolved = resolve_components(&components, ResolveMode::ComponentsOnly).unwrap();
+
+        let foo = resolved.schemas.get("Foo").unwrap().as_item().unwrap();
+        let bar = match &all_of_refs(foo)[0] {
+            ReferenceOr::DereferencedReference { item, .. } => item,
+            _ => panic!("Foo's ref to Bar should have resolved"),
+        };
+        assert!(
+            matches!(all_of_refs(bar)[0], ReferenceOr::Reference { .. }),
+            "Bar's ref back to Foo closes the cycle and must stay a plain Reference"
+        );
+    }
+
+    #[test]
+    fn dangling_pointer_is_reported() {
+        let mut components = Components::default();
+        components.schemas.insert(
+            "Foo".to_owned(),
+            ReferenceOr::Reference {
+                reference: "#/components/schemas/DoesNotExist".to_owned(),
+            },
+        );
+
+        let err = resolve_components(&components, ResolveMode::ComponentsOnly).unwrap_err();
+        assert_eq!(
+            err,
+            ResolveError::DanglingReference("#/components/schemas/DoesNotExist".to_owned())
+        );
+    }
+
+    #[test]
+    fn external_file_references_are_left_untouched_in_components_only_mode() {
+        let mut components = Components::default();
+        components.schemas.insert(
+            "Foo".to_owned(),
+            ReferenceOr::Item(schema_all_of(vec!["other.yaml#/components/schemas/Bar"])),
+        );
+
+        let resolved = resolve_components(&components, ResolveMode::ComponentsOnly).unwrap();
+        let foo = resolved.schemas.get("Foo").unwrap().as_item().unwrap();
+        match &all_of_refs(foo)[0] {
+            ReferenceOr::Reference { reference } => {
+                assert_eq!(reference, "other.yaml#/components/schemas/Bar")
+            }
+            _ => panic!("external references must not be rewritten in ComponentsOnly mode"),
+        }
+    }
+
+    #[test]
+    fn external_file_references_error_in_strict_mode() {
+        let mut components = Components::default();
+        components.schemas.insert(
+            "Foo".to_owned(),
+            ReferenceOr::Item(schema_all_of(vec!["other.yaml#/components/schemas/Bar"])),
+        );
+
+        let err = resolve_components(&components, ResolveMode::Strict).unwrap_err();
+        assert_eq!(
+            err,
+            ResolveError::UnresolvableReference("other.yaml#/components/schemas/Bar".to_owned())
+        );
+    }
+
+    #[test]
+    fn tilde_and_slash_are_decoded_in_pointer_segments() {
+        let mut components = Components::default();
+        components
+            .schemas
+            .insert("a/b~c".to_owned(), ReferenceOr::Item(schema_all_of(vec![])));
+        components.schemas.insert(
+            "Foo".to_owned(),
+            ReferenceOr::Item(schema_all_of(vec!["#/components/schemas/a~1b~0c"])),
+        );
+
+        let resolved = resolve_components(&components, ResolveMode::ComponentsOnly).unwrap();
+        let foo = resolved.schemas.get("Foo").unwrap().as_item().unwrap();
+        assert!(matches!(
+            all_of_refs(foo)[0],
+            ReferenceOr::DereferencedReference { .. }
+        ));
+    }
+}
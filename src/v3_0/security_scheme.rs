@@ -107,6 +107,215 @@ pub enum OAuth2Flow {
     },
 }
 
+/// The subset of the OAuth 2.0 Authorization Server Metadata
+/// ([RFC 8414](https://www.rfc-editor.org/rfc/rfc8414)) document this crate
+/// needs to turn an OpenID Connect discovery URL into concrete
+/// [`OAuth2Flows`].
+#[cfg(feature = "oidc-discovery")]
+#[derive(Debug, Clone, Deserialize)]
+struct AuthorizationServerMetadata {
+    issuer: String,
+    authorization_endpoint: Option<String>,
+    token_endpoint: Option<String>,
+    #[serde(default)]
+    scopes_supported: Vec<String>,
+    #[serde(default)]
+    grant_types_supported: Vec<String>,
+    #[serde(default)]
+    response_types_supported: Vec<String>,
+}
+
+/// Error produced by [`SecurityScheme::discover_oauth2_flows`].
+#[cfg(feature = "oidc-discovery")]
+#[derive(Debug)]
+pub enum DiscoveryError {
+    /// Fetching or deserializing the discovery document failed.
+    Request(reqwest::Error),
+    /// The discovery document didn't advertise an endpoint a selected
+    /// grant type requires.
+    MissingEndpoint(&'static str),
+}
+
+#[cfg(feature = "oidc-discovery")]
+impl std::fmt::Display for DiscoveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscoveryError::Request(e) => write!(f, "openid-configuration discovery failed: {}", e),
+            DiscoveryError::MissingEndpoint(name) => {
+                write!(f, "discovery document is missing required endpoint '{}'", name)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "oidc-discovery")]
+impl std::error::Error for DiscoveryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DiscoveryError::Request(e) => Some(e),
+            DiscoveryError::MissingEndpoint(_) => None,
+        }
+    }
+}
+
+#[cfg(feature = "oidc-discovery")]
+impl From<reqwest::Error> for DiscoveryError {
+    fn from(e: reqwest::Error) -> Self {
+        DiscoveryError::Request(e)
+    }
+}
+
+#[cfg(feature = "oidc-discovery")]
+impl SecurityScheme {
+    /// Resolves this scheme into concrete OAuth2 flows.
+    ///
+    /// For [`SecurityScheme::OpenIDConnect`], this fetches
+    /// `open_id_connect_url` (the provider's
+    /// `.well-known/openid-configuration` document, per OIDC Discovery),
+    /// and maps its advertised grant types onto [`OAuth2Flow`] variants so
+    /// that callers can treat an OIDC scheme the same as an
+    /// explicitly-declared [`SecurityScheme::OAuth2`] one. Only the
+    /// `authorization_code`, `client_credentials` and `password` grants are
+    /// mapped; `OAuth2Flows::implicit` is always `None`, even when the
+    /// implicit grant is advertised. Returns `Ok(None)` for every other
+    /// variant.
+    pub async fn discover_oauth2_flows(&self) -> Result<Option<OAuth2Flows>, DiscoveryError> {
+        let SecurityScheme::OpenIDConnect {
+            open_id_connect_url, ..
+        } = self
+        else {
+            return Ok(None);
+        };
+
+        let metadata: AuthorizationServerMetadata =
+            reqwest::get(open_id_connect_url).await?.json().await?;
+        Ok(Some(oauth2_flows_from_metadata(&metadata)?))
+    }
+}
+
+#[cfg(feature = "oidc-discovery")]
+fn oauth2_flows_from_metadata(
+    metadata: &AuthorizationServerMetadata,
+) -> Result<OAuth2Flows, DiscoveryError> {
+    let scopes: IndexMap<String, String> = metadata
+        .scopes_supported
+        .iter()
+        .map(|scope| (scope.clone(), String::new()))
+        .collect();
+
+    // RFC 8414 says an absent `grant_types_supported` defaults to exactly
+    // `["authorization_code", "implicit"]`, not "every grant type".
+    const DEFAULT_GRANTS: [&str; 2] = ["authorization_code", "implicit"];
+    let supports = |grant: &str| {
+        if metadata.grant_types_supported.is_empty() {
+            DEFAULT_GRANTS.contains(&grant)
+        } else {
+            metadata.grant_types_supported.iter().any(|g| g == grant)
+        }
+    };
+    let token_endpoint = || {
+        metadata
+            .token_endpoint
+            .clone()
+            .ok_or(DiscoveryError::MissingEndpoint("token_endpoint"))
+    };
+
+    let authorization_code = if supports("authorization_code")
+        && metadata.response_types_supported.iter().any(|t| t == "code")
+    {
+        Some(OAuth2Flow::AuthorizationCode {
+            authorization_url: metadata
+                .authorization_endpoint
+                .clone()
+                .ok_or(DiscoveryError::MissingEndpoint("authorization_endpoint"))?,
+            token_url: token_endpoint()?,
+            refresh_url: None,
+            scopes: scopes.clone(),
+        })
+    } else {
+        None
+    };
+
+    let client_credentials = if supports("client_credentials") {
+        Some(OAuth2Flow::ClientCredentials {
+            refresh_url: None,
+            token_url: token_endpoint()?,
+            scopes: scopes.clone(),
+        })
+    } else {
+        None
+    };
+
+    let password = if supports("password") {
+        Some(OAuth2Flow::Password {
+            refresh_url: None,
+            token_url: token_endpoint()?,
+            scopes: scopes.clone(),
+        })
+    } else {
+        None
+    };
+
+    Ok(OAuth2Flows {
+        implicit: None,
+        password,
+        client_credentials,
+        authorization_code,
+    })
+}
+
+#[cfg(all(test, feature = "oidc-discovery"))]
+mod oidc_discovery_tests {
+    use super::*;
+
+    fn metadata() -> AuthorizationServerMetadata {
+        AuthorizationServerMetadata {
+            issuer: "https://issuer.example.com".to_owned(),
+            authorization_endpoint: Some("https://issuer.example.com/authorize".to_owned()),
+            token_endpoint: Some("https://issuer.example.com/token".to_owned()),
+            scopes_supported: vec!["openid".to_owned()],
+            grant_types_supported: Vec::new(),
+            response_types_supported: vec!["code".to_owned()],
+        }
+    }
+
+    #[test]
+    fn defaults_to_authorization_code_and_implicit_when_grant_types_omitted() {
+        let flows = oauth2_flows_from_metadata(&metadata()).unwrap();
+        assert!(flows.authorization_code.is_some());
+        assert!(flows.password.is_none(), "password is not in the RFC 8414 default grant set");
+        assert!(
+            flows.client_credentials.is_none(),
+            "client_credentials is not in the RFC 8414 default grant set"
+        );
+    }
+
+    #[test]
+    fn explicit_grant_types_are_honored_instead_of_the_default() {
+        let mut metadata = metadata();
+        metadata.grant_types_supported = vec!["client_credentials".to_owned()];
+        let flows = oauth2_flows_from_metadata(&metadata).unwrap();
+        assert!(flows.client_credentials.is_some());
+        assert!(flows.authorization_code.is_none());
+    }
+
+    #[test]
+    fn authorization_code_requires_code_in_response_types_supported() {
+        let mut metadata = metadata();
+        metadata.response_types_supported = vec!["token".to_owned()];
+        let flows = oauth2_flows_from_metadata(&metadata).unwrap();
+        assert!(flows.authorization_code.is_none());
+    }
+
+    #[test]
+    fn missing_token_endpoint_is_an_error() {
+        let mut metadata = metadata();
+        metadata.token_endpoint = None;
+        let err = oauth2_flows_from_metadata(&metadata).unwrap_err();
+        assert!(matches!(err, DiscoveryError::MissingEndpoint("token_endpoint")));
+    }
+}
+
 #[cfg(feature = "conversions")]
 mod conversions {
     use super::*;
@@ -1,6 +1,7 @@
 use crate::v3_0::*;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// An object representing a Server.
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -26,6 +27,115 @@ pub struct Server {
     pub extensions: IndexMap<String, serde_json::Value>,
 }
 
+/// Error produced by [`Server::expand_url`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpandError {
+    /// The URL contains a `{name}` token with no matching [`ServerVariable`].
+    /// An override alone does not define a variable.
+    UndefinedVariable(String),
+    /// The URL contains a `{` with no matching closing `}`.
+    UnterminatedToken(String),
+    /// The value to substitute (an override, or the variable's own
+    /// `default`) is not one of the values listed in the variable's `enum`.
+    InvalidValue { variable: String, value: String },
+}
+
+impl fmt::Display for ExpandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpandError::UndefinedVariable(name) => {
+                write!(f, "no server variable named '{}'", name)
+            }
+            ExpandError::UnterminatedToken(rest) => {
+                write!(f, "unterminated '{{' in server URL near '{}'", rest)
+            }
+            ExpandError::InvalidValue { variable, value } => write!(
+                f,
+                "'{}' is not a valid value for server variable '{}'",
+                value, variable
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExpandError {}
+
+impl Server {
+    /// Expands every `{name}` token in [`Server::url`], substituting the
+    /// caller's `overrides` or, failing that, the matching
+    /// [`ServerVariable::default`].
+    ///
+    /// Returns an error if a token has no matching variable definition, or
+    /// if the value being substituted (override or default) is not one of
+    /// the values listed in that variable's `enum`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use indexmap::IndexMap;
+    /// # use openapiv3::v3_0::{Server, ServerVariable};
+    /// let mut variables = IndexMap::new();
+    /// variables.insert(
+    ///     "version".to_owned(),
+    ///     ServerVariable {
+    ///         default: "v1".to_owned(),
+    ///         ..Default::default()
+    ///     },
+    /// );
+    /// let server = Server {
+    ///     url: "https://api.example.com/{version}".to_owned(),
+    ///     variables: Some(variables),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     server.expand_url(&IndexMap::new()).unwrap(),
+    ///     "https://api.example.com/v1"
+    /// );
+    /// ```
+    pub fn expand_url(&self, overrides: &IndexMap<String, String>) -> Result<String, ExpandError> {
+        let mut result = String::with_capacity(self.url.len());
+        let mut remaining = self.url.as_str();
+        while let Some(open) = remaining.find('{') {
+            result.push_str(&remaining[..open]);
+            let after_open = &remaining[open + 1..];
+            let close = after_open
+                .find('}')
+                .ok_or_else(|| ExpandError::UnterminatedToken(after_open.to_owned()))?;
+            let name = &after_open[..close];
+            result.push_str(&self.resolve_variable(name, overrides)?);
+            remaining = &after_open[close + 1..];
+        }
+        result.push_str(remaining);
+        Ok(result)
+    }
+
+    fn resolve_variable(
+        &self,
+        name: &str,
+        overrides: &IndexMap<String, String>,
+    ) -> Result<String, ExpandError> {
+        let variable = self
+            .variables
+            .as_ref()
+            .and_then(|variables| variables.get(name))
+            .ok_or_else(|| ExpandError::UndefinedVariable(name.to_owned()))?;
+
+        let value = overrides
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| variable.default.clone());
+
+        if !variable.enumeration.is_empty() && !variable.enumeration.contains(&value) {
+            return Err(ExpandError::InvalidValue {
+                variable: name.to_owned(),
+                value,
+            });
+        }
+
+        Ok(value)
+    }
+}
+
 #[cfg(feature = "conversions")]
 use crate::v3_1;
 
@@ -0,0 +1,622 @@
+use crate::v3_0::*;
+use indexmap::IndexMap;
+
+/// A single problem found by [`validate`], located by a JSON-Pointer-style
+/// path into the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Where the problem was found, e.g. `#/components/schemas/Pet`.
+    pub location: String,
+    /// A human-readable description of what's wrong.
+    pub message: String,
+}
+
+const RESERVED_HEADER_NAMES: [&str; 3] = ["content-type", "accept", "authorization"];
+
+/// Walks `spec` and collects structural and reference-integrity problems
+/// instead of silently accepting an invalid document.
+///
+/// Checks performed:
+/// - header parameters/headers named after a reserved header
+///   (`Content-Type`, `Accept`, `Authorization`, compared
+///   case-insensitively, since those are controlled elsewhere in the spec)
+/// - every `$ref` resolves to an entry that actually exists in `Components`
+/// - every `OAuth2Flow` has its required URLs, and they are absolute
+/// - map keys that collide only by case (e.g. two headers named `X-Foo`
+///   and `x-foo`), which HTTP treats as the same header
+///
+/// Both `spec.components` and every operation under `spec.paths` (their
+/// parameters, request bodies, and responses) are walked, since
+/// operation-level parameters are where header names are most often
+/// declared in practice.
+pub fn validate(spec: &OpenAPI) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let components = spec.components.clone().unwrap_or_default();
+    if let Some(components) = &spec.components {
+        validate_components(components, &mut errors);
+    }
+    validate_paths(&spec.paths, &components, &mut errors);
+    errors
+}
+
+fn validate_paths(paths: &Paths, components: &Components, errors: &mut Vec<ValidationError>) {
+    for (path, item) in &paths.paths {
+        let location = format!("#/paths/{}", encode_token(path));
+        match item {
+            // A `$ref`'d path item points outside this document (Path Item
+            // Objects aren't part of `Components`); nothing local to check.
+            ReferenceOr::Reference { .. } => {}
+            ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+                validate_path_item(item, &location, components, errors)
+            }
+        }
+    }
+}
+
+fn validate_path_item(item: &PathItem, location: &str, components: &Components, errors: &mut Vec<ValidationError>) {
+    for (index, parameter) in item.parameters.iter().enumerate() {
+        validate_parameter_ref(parameter, &format!("{}/parameters/{}", location, index), components, errors);
+    }
+    for (method, operation) in [
+        ("get", &item.get),
+        ("put", &item.put),
+        ("post", &item.post),
+        ("delete", &item.delete),
+        ("options", &item.options),
+        ("head", &item.head),
+        ("patch", &item.patch),
+        ("trace", &item.trace),
+    ] {
+        if let Some(operation) = operation {
+            validate_operation(operation, &format!("{}/{}", location, method), components, errors);
+        }
+    }
+}
+
+fn validate_operation(op: &Operation, location: &str, components: &Components, errors: &mut Vec<ValidationError>) {
+    for (index, parameter) in op.parameters.iter().enumerate() {
+        validate_parameter_ref(parameter, &format!("{}/parameters/{}", location, index), components, errors);
+    }
+    if let Some(request_body) = &op.request_body {
+        let request_body_location = format!("{}/requestBody", location);
+        match request_body {
+            ReferenceOr::Reference { reference } => {
+                check_reference(reference, components, &request_body_location, errors)
+            }
+            ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+                validate_content_map(&item.content, &request_body_location, components, errors)
+            }
+        }
+    }
+    let responses_location = format!("{}/responses", location);
+    if let Some(default) = &op.responses.default {
+        validate_response_ref(default, &format!("{}/default", responses_location), components, errors);
+    }
+    for (status, response) in &op.responses.responses {
+        let status_location = format!("{}/{}", responses_location, encode_token(&status.to_string()));
+        validate_response_ref(response, &status_location, components, errors);
+    }
+    for (name, callback) in &op.callbacks {
+        if let ReferenceOr::Reference { reference } = callback {
+            let callback_location = format!("{}/callbacks/{}", location, encode_token(name));
+            check_reference(reference, components, &callback_location, errors);
+        }
+    }
+}
+
+fn validate_parameter_ref(
+    parameter: &ReferenceOr<Parameter>,
+    location: &str,
+    components: &Components,
+    errors: &mut Vec<ValidationError>,
+) {
+    match parameter {
+        ReferenceOr::Reference { reference } => check_reference(reference, components, location, errors),
+        ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+            validate_parameter(item, location, components, errors)
+        }
+    }
+}
+
+fn validate_response_ref(
+    response: &ReferenceOr<Response>,
+    location: &str,
+    components: &Components,
+    errors: &mut Vec<ValidationError>,
+) {
+    match response {
+        ReferenceOr::Reference { reference } => check_reference(reference, components, location, errors),
+        ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+            validate_response(item, location, components, errors)
+        }
+    }
+}
+
+fn validate_components(components: &Components, errors: &mut Vec<ValidationError>) {
+    check_case_insensitive_duplicates("#/components/schemas", components.schemas.keys(), errors);
+    check_case_insensitive_duplicates("#/components/responses", components.responses.keys(), errors);
+    check_case_insensitive_duplicates("#/components/parameters", components.parameters.keys(), errors);
+    check_case_insensitive_duplicates("#/components/examples", components.examples.keys(), errors);
+    check_case_insensitive_duplicates(
+        "#/components/requestBodies",
+        components.request_bodies.keys(),
+        errors,
+    );
+    check_case_insensitive_duplicates("#/components/headers", components.headers.keys(), errors);
+    check_case_insensitive_duplicates("#/components/links", components.links.keys(), errors);
+    check_case_insensitive_duplicates("#/components/callbacks", components.callbacks.keys(), errors);
+    check_case_insensitive_duplicates(
+        "#/components/securitySchemes",
+        components.security_schemes.keys(),
+        errors,
+    );
+
+    for (name, scheme) in &components.security_schemes {
+        let location = format!("#/components/securitySchemes/{}", encode_token(name));
+        match scheme {
+            ReferenceOr::Reference { reference } => check_reference(reference, components, &location, errors),
+            ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+                validate_security_scheme(item, &location, errors)
+            }
+        }
+    }
+
+    for (name, schema) in &components.schemas {
+        let location = format!("#/components/schemas/{}", encode_token(name));
+        validate_ref_or_schema(schema, &location, components, errors);
+    }
+
+    for (name, response) in &components.responses {
+        let location = format!("#/components/responses/{}", encode_token(name));
+        validate_response_ref(response, &location, components, errors);
+    }
+
+    for (name, parameter) in &components.parameters {
+        let location = format!("#/components/parameters/{}", encode_token(name));
+        validate_parameter_ref(parameter, &location, components, errors);
+    }
+
+    for (name, request_body) in &components.request_bodies {
+        let location = format!("#/components/requestBodies/{}", encode_token(name));
+        match request_body {
+            ReferenceOr::Reference { reference } => check_reference(reference, components, &location, errors),
+            ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+                validate_content_map(&item.content, &location, components, errors)
+            }
+        }
+    }
+
+    for (name, header) in &components.headers {
+        let location = format!("#/components/headers/{}", encode_token(name));
+        check_reserved_header_name(name, &location, errors);
+        match header {
+            ReferenceOr::Reference { reference } => check_reference(reference, components, &location, errors),
+            ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+                validate_header(item, &location, components, errors)
+            }
+        }
+    }
+
+    for (name, link) in &components.links {
+        if let ReferenceOr::Reference { reference } = link {
+            let location = format!("#/components/links/{}", encode_token(name));
+            check_reference(reference, components, &location, errors);
+        }
+    }
+
+    for (name, callback) in &components.callbacks {
+        if let ReferenceOr::Reference { reference } = callback {
+            let location = format!("#/components/callbacks/{}", encode_token(name));
+            check_reference(reference, components, &location, errors);
+        }
+    }
+
+    for (name, example) in &components.examples {
+        if let ReferenceOr::Reference { reference } = example {
+            let location = format!("#/components/examples/{}", encode_token(name));
+            check_reference(reference, components, &location, errors);
+        }
+    }
+}
+
+fn validate_security_scheme(scheme: &SecurityScheme, location: &str, errors: &mut Vec<ValidationError>) {
+    let SecurityScheme::OAuth2 { flows, .. } = scheme else {
+        return;
+    };
+    if let Some(flow) = &flows.implicit {
+        validate_oauth2_flow(flow, &format!("{}/flows/implicit", location), errors);
+    }
+    if let Some(flow) = &flows.password {
+        validate_oauth2_flow(flow, &format!("{}/flows/password", location), errors);
+    }
+    if let Some(flow) = &flows.client_credentials {
+        validate_oauth2_flow(flow, &format!("{}/flows/clientCredentials", location), errors);
+    }
+    if let Some(flow) = &flows.authorization_code {
+        validate_oauth2_flow(flow, &format!("{}/flows/authorizationCode", location), errors);
+    }
+}
+
+fn validate_oauth2_flow(flow: &OAuth2Flow, location: &str, errors: &mut Vec<ValidationError>) {
+    match flow {
+        OAuth2Flow::Implicit { authorization_url, .. } => {
+            check_absolute_url(authorization_url, &format!("{}/authorizationUrl", location), errors);
+        }
+        OAuth2Flow::Password { token_url, .. } | OAuth2Flow::ClientCredentials { token_url, .. } => {
+            check_absolute_url(token_url, &format!("{}/tokenUrl", location), errors);
+        }
+        OAuth2Flow::AuthorizationCode {
+            authorization_url,
+            token_url,
+            ..
+        } => {
+            check_absolute_url(authorization_url, &format!("{}/authorizationUrl", location), errors);
+            check_absolute_url(token_url, &format!("{}/tokenUrl", location), errors);
+        }
+    }
+}
+
+fn check_absolute_url(url: &str, location: &str, errors: &mut Vec<ValidationError>) {
+    if url.is_empty() {
+        errors.push(ValidationError {
+            location: location.to_owned(),
+            message: "is required but empty".to_owned(),
+        });
+    } else if !is_absolute_url(url) {
+        errors.push(ValidationError {
+            location: location.to_owned(),
+            message: format!("'{}' is not an absolute URL", url),
+        });
+    }
+}
+
+fn is_absolute_url(url: &str) -> bool {
+    match url.split_once("://") {
+        Some((scheme, _)) => {
+            !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+fn validate_parameter(parameter: &Parameter, location: &str, components: &Components, errors: &mut Vec<ValidationError>) {
+    let data = match parameter {
+        Parameter::Query { parameter_data, .. }
+        | Parameter::Header { parameter_data, .. }
+        | Parameter::Path { parameter_data, .. }
+        | Parameter::Cookie { parameter_data, .. } => parameter_data,
+    };
+    if matches!(parameter, Parameter::Header { .. }) {
+        check_reserved_header_name(&data.name, location, errors);
+    }
+    validate_parameter_schema_or_content(&data.format, location, components, errors);
+    validate_example_map(&data.examples, location, components, errors);
+}
+
+fn validate_header(header: &Header, location: &str, components: &Components, errors: &mut Vec<ValidationError>) {
+    validate_parameter_schema_or_content(&header.format, location, components, errors);
+    validate_example_map(&header.examples, location, components, errors);
+}
+
+fn validate_parameter_schema_or_content(
+    format: &ParameterSchemaOrContent,
+    location: &str,
+    components: &Components,
+    errors: &mut Vec<ValidationError>,
+) {
+    match format {
+        ParameterSchemaOrContent::Schema(schema) => validate_ref_or_schema(schema, location, components, errors),
+        ParameterSchemaOrContent::Content(content) => validate_content_map(content, location, components, errors),
+    }
+}
+
+fn validate_response(response: &Response, location: &str, components: &Components, errors: &mut Vec<ValidationError>) {
+    check_case_insensitive_duplicates(&format!("{}/headers", location), response.headers.keys(), errors);
+    for (name, header) in &response.headers {
+        let header_location = format!("{}/headers/{}", location, encode_token(name));
+        check_reserved_header_name(name, &header_location, errors);
+        match header {
+            ReferenceOr::Reference { reference } => check_reference(reference, components, &header_location, errors),
+            ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+                validate_header(item, &header_location, components, errors)
+            }
+        }
+    }
+    validate_content_map(&response.content, location, components, errors);
+    for (name, link) in &response.links {
+        if let ReferenceOr::Reference { reference } = link {
+            let link_location = format!("{}/links/{}", location, encode_token(name));
+            check_reference(reference, components, &link_location, errors);
+        }
+    }
+}
+
+fn validate_content_map(
+    content: &IndexMap<String, MediaType>,
+    location: &str,
+    components: &Components,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (media_type_name, media_type) in content {
+        let media_type_location = format!("{}/content/{}", location, encode_token(media_type_name));
+        if let Some(schema) = &media_type.schema {
+            validate_ref_or_schema(schema, &media_type_location, components, errors);
+        }
+        validate_example_map(&media_type.examples, &media_type_location, components, errors);
+    }
+}
+
+fn validate_example_map(
+    examples: &IndexMap<String, ReferenceOr<Example>>,
+    location: &str,
+    components: &Components,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (name, example) in examples {
+        if let ReferenceOr::Reference { reference } = example {
+            let example_location = format!("{}/examples/{}", location, encode_token(name));
+            check_reference(reference, components, &example_location, errors);
+        }
+    }
+}
+
+fn validate_ref_or_schema(
+    schema: &ReferenceOr<Schema>,
+    location: &str,
+    components: &Components,
+    errors: &mut Vec<ValidationError>,
+) {
+    match schema {
+        ReferenceOr::Reference { reference } => check_reference(reference, components, location, errors),
+        ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+            validate_schema(item, location, components, errors)
+        }
+    }
+}
+
+fn validate_boxed_schema(
+    schema: &ReferenceOr<Box<Schema>>,
+    location: &str,
+    components: &Components,
+    errors: &mut Vec<ValidationError>,
+) {
+    match schema {
+        ReferenceOr::Reference { reference } => check_reference(reference, components, location, errors),
+        ReferenceOr::Item(item) | ReferenceOr::DereferencedReference { item, .. } => {
+            validate_schema(item, location, components, errors)
+        }
+    }
+}
+
+fn validate_schema(schema: &Schema, location: &str, components: &Components, errors: &mut Vec<ValidationError>) {
+    match &schema.schema_kind {
+        SchemaKind::Type(Type::Object(obj)) => {
+            for (name, property) in &obj.properties {
+                let property_location = format!("{}/properties/{}", location, encode_token(name));
+                validate_boxed_schema(property, &property_location, components, errors);
+            }
+            if let Some(AdditionalProperties::Schema(schema)) = &obj.additional_properties {
+                validate_boxed_schema(
+                    schema,
+                    &format!("{}/additionalProperties", location),
+                    components,
+                    errors,
+                );
+            }
+        }
+        SchemaKind::Type(Type::Array(arr)) => {
+            if let Some(items) = &arr.items {
+                validate_boxed_schema(items, &format!("{}/items", location), components, errors);
+            }
+        }
+        SchemaKind::AllOf { all_of } => validate_schema_list(all_of, &format!("{}/allOf", location), components, errors),
+        SchemaKind::AnyOf { any_of } => validate_schema_list(any_of, &format!("{}/anyOf", location), components, errors),
+        SchemaKind::OneOf { one_of } => validate_schema_list(one_of, &format!("{}/oneOf", location), components, errors),
+        SchemaKind::Not { not } => validate_ref_or_schema(not, &format!("{}/not", location), components, errors),
+        _ => {}
+    }
+}
+
+fn validate_schema_list(
+    schemas: &[ReferenceOr<Schema>],
+    location: &str,
+    components: &Components,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (index, schema) in schemas.iter().enumerate() {
+        validate_ref_or_schema(schema, &format!("{}/{}", location, index), components, errors);
+    }
+}
+
+fn check_reserved_header_name(name: &str, location: &str, errors: &mut Vec<ValidationError>) {
+    if RESERVED_HEADER_NAMES.contains(&name.to_ascii_lowercase().as_str()) {
+        errors.push(ValidationError {
+            location: location.to_owned(),
+            message: format!("'{}' is a reserved header and cannot be declared here", name),
+        });
+    }
+}
+
+/// Flags keys in `keys` that only differ by case, since HTTP header names
+/// (and, by extension, the component names we use to describe them) are
+/// case-insensitive.
+fn check_case_insensitive_duplicates<'a>(
+    location: &str,
+    keys: impl Iterator<Item = &'a String>,
+    errors: &mut Vec<ValidationError>,
+) {
+    let mut seen: IndexMap<String, &str> = IndexMap::new();
+    for key in keys {
+        let lower = key.to_ascii_lowercase();
+        match seen.get(lower.as_str()) {
+            Some(&first) if first != key.as_str() => errors.push(ValidationError {
+                location: location.to_owned(),
+                message: format!("'{}' collides with '{}' when compared case-insensitively", key, first),
+            }),
+            Some(_) => {}
+            None => {
+                seen.insert(lower, key.as_str());
+            }
+        }
+    }
+}
+
+fn check_reference(reference: &str, components: &Components, location: &str, errors: &mut Vec<ValidationError>) {
+    let Some(fragment) = reference.strip_prefix('#') else {
+        // References into other files can't be checked without fetching them.
+        return;
+    };
+    let mut segments = fragment.split('/').filter(|s| !s.is_empty());
+    let (Some("components"), Some(kind), Some(name), None) =
+        (segments.next(), segments.next(), segments.next(), segments.next())
+    else {
+        errors.push(ValidationError {
+            location: location.to_owned(),
+            message: format!("'{}' is not a well-formed #/components/<kind>/<name> reference", reference),
+        });
+        return;
+    };
+    let name = decode_token(name);
+    let exists = match kind {
+        "schemas" => components.schemas.contains_key(&name),
+        "responses" => components.responses.contains_key(&name),
+        "parameters" => components.parameters.contains_key(&name),
+        "examples" => components.examples.contains_key(&name),
+        "requestBodies" => components.request_bodies.contains_key(&name),
+        "headers" => components.headers.contains_key(&name),
+        "links" => components.links.contains_key(&name),
+        "callbacks" => components.callbacks.contains_key(&name),
+        "securitySchemes" => components.security_schemes.contains_key(&name),
+        _ => {
+            errors.push(ValidationError {
+                location: location.to_owned(),
+                message: format!("'{}' references an unknown components section '{}'", reference, kind),
+            });
+            return;
+        }
+    };
+    if !exists {
+        errors.push(ValidationError {
+            location: location.to_owned(),
+            message: format!("'{}' does not resolve to anything", reference),
+        });
+    }
+}
+
+fn decode_token(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+fn encode_token(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_parameter(name: &str) -> Parameter {
+        Parameter::Header {
+            parameter_data: ParameterData {
+                name: name.to_owned(),
+                description: None,
+                required: false,
+                deprecated: None,
+                format: ParameterSchemaOrContent::Content(IndexMap::new()),
+                example: None,
+                examples: IndexMap::new(),
+                explode: None,
+                extensions: IndexMap::new(),
+            },
+            style: HeaderStyle::Simple,
+        }
+    }
+
+    #[test]
+    fn reserved_header_name_on_an_operation_parameter_is_flagged() {
+        let components = Components::default();
+        let operation = Operation {
+            parameters: vec![ReferenceOr::Item(header_parameter("Authorization"))],
+            ..Default::default()
+        };
+        let mut paths_map = IndexMap::new();
+        paths_map.insert(
+            "/widgets".to_owned(),
+            ReferenceOr::Item(PathItem {
+                get: Some(operation),
+                ..Default::default()
+            }),
+        );
+        let paths = Paths {
+            paths: paths_map,
+            ..Default::default()
+        };
+
+        let mut errors = Vec::new();
+        validate_paths(&paths, &components, &mut errors);
+
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.location == "#/paths/~1widgets/get/parameters/0"),
+            "expected a reserved-header error at the operation parameter, got {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn dangling_reference_is_flagged() {
+        let mut components = Components::default();
+        components.responses.insert(
+            "NotFound".to_owned(),
+            ReferenceOr::Reference {
+                reference: "#/components/responses/DoesNotExist".to_owned(),
+            },
+        );
+
+        let mut errors = Vec::new();
+        validate_components(&components, &mut errors);
+
+        assert!(errors.iter().any(|e| {
+            e.location == "#/components/responses/NotFound" && e.message.contains("DoesNotExist")
+        }));
+    }
+
+    #[test]
+    fn non_absolute_oauth2_token_url_is_flagged() {
+        let mut components = Components::default();
+        components.security_schemes.insert(
+            "oauth".to_owned(),
+            ReferenceOr::Item(SecurityScheme::OAuth2 {
+                flows: OAuth2Flows {
+                    implicit: None,
+                    password: None,
+                    client_credentials: Some(OAuth2Flow::ClientCredentials {
+                        refresh_url: None,
+                        token_url: "/token".to_owned(),
+                        scopes: IndexMap::new(),
+                    }),
+                    authorization_code: None,
+                },
+                description: None,
+                extensions: IndexMap::new(),
+            }),
+        );
+
+        let mut errors = Vec::new();
+        validate_components(&components, &mut errors);
+
+        assert!(errors.iter().any(|e| {
+            e.location == "#/components/securitySchemes/oauth/flows/clientCredentials/tokenUrl"
+        }));
+    }
+
+    #[test]
+    fn case_insensitive_duplicate_keys_are_flagged() {
+        let mut errors = Vec::new();
+        let keys = vec!["X-Foo".to_owned(), "x-foo".to_owned()];
+        check_case_insensitive_duplicates("#/components/headers", keys.iter(), &mut errors);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("X-Foo"));
+    }
+}